@@ -0,0 +1,32 @@
+//! Virtual key codes, shared across backends.
+//!
+//! These mirror the Win32 `VK_*` constants, since that's the numbering
+//! `key::Press` uses internally for `Code::VirtualKey`. Non-windows backends
+//! translate these into their own native keycodes.
+
+/// A platform-independent virtual key, numbered after the Win32 `VK_*` constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u16)]
+pub enum VirtualKey {
+    Backspace = 0x08,
+    Tab = 0x09,
+    Enter = 0x0D,
+    Shift = 0x10,
+    Control = 0x11,
+    Alt = 0x12,
+    Escape = 0x1B,
+    Space = 0x20,
+    PageUp = 0x21,
+    PageDown = 0x22,
+    End = 0x23,
+    Home = 0x24,
+    Left = 0x25,
+    Up = 0x26,
+    Right = 0x27,
+    Down = 0x28,
+    Delete = 0x2E,
+    LeftShift = 0xA0,
+    RightShift = 0xA1,
+    LeftControl = 0xA2,
+    RightControl = 0xA3,
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// Errors that can occur while resolving or playing back a `Chord` on backends
+/// that must translate characters into keycodes themselves (the `uinput` and
+/// Wayland backends), rather than letting the OS do it for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisplayError {
+    /// No keycode (or keysym, on Wayland) could be found for this character.
+    UnsupportedChar(char),
+
+    /// A `Code::VirtualKey`/`Code::ScanCode` value has no known mapping onto
+    /// the target backend's native keycodes.
+    UnsupportedVirtualKey(u16),
+
+    /// A `Code::UnicodeKey` held a UTF-16 code unit (e.g. half of a
+    /// surrogate pair) that doesn't decode to a real character on its own.
+    InvalidCodeUnit(u16),
+
+    /// The backend's virtual input device could not be created or driven
+    /// (e.g. `/dev/uinput` is missing or not writable).
+    Device(String),
+
+    /// Connecting to, or negotiating protocol globals with, the display
+    /// server failed.
+    Connection(String),
+
+    /// An I/O error occurred while preparing data for the backend (e.g.
+    /// writing a synthesized keymap to a temporary file).
+    Io(String),
+
+    /// A backend-specific capacity limit was exceeded (e.g. the number of
+    /// live keycodes a synthesized Wayland keymap can track at once).
+    CapacityExceeded(String),
+}
+
+impl fmt::Display for DisplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisplayError::UnsupportedChar(c) => {
+                write!(f, "no keycode mapping for character {c:?}")
+            }
+            DisplayError::UnsupportedVirtualKey(vk) => {
+                write!(f, "no keycode mapping for virtual key {vk:#06x}")
+            }
+            DisplayError::InvalidCodeUnit(unit) => {
+                write!(f, "UTF-16 code unit {unit:#06x} is not a standalone character")
+            }
+            DisplayError::Device(msg) => write!(f, "virtual input device error: {msg}"),
+            DisplayError::Connection(msg) => write!(f, "display server connection error: {msg}"),
+            DisplayError::Io(msg) => write!(f, "I/O error: {msg}"),
+            DisplayError::CapacityExceeded(msg) => write!(f, "capacity exceeded: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for DisplayError {}
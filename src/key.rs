@@ -10,6 +10,16 @@ use std::time::Duration;
 pub enum Code {
     VirtualKey(u16),
     UnicodeKey(u16),
+
+    /// A key sent by hardware scancode, with `KEYEVENTF_SCANCODE`, instead of
+    /// as a virtual-key or unicode code point. Some applications (particularly
+    /// games) filter out synthetic virtual-key/unicode input and only react
+    /// to these, since real keyboards report scancodes.
+    ///
+    /// The value is a virtual-key code, which the backend translates into
+    /// the matching scancode at send time; a raw hardware scancode cannot be
+    /// expressed with this variant.
+    ScanCode(u16),
 }
 
 /// Keypress events are virtual or unicode key events, over some duration
@@ -26,6 +36,10 @@ pub struct Press {
 
     /// The duration to hold the key down for
     pub duration: Option<Duration>,
+
+    /// If set, this press is repeated `count` times, spaced `interval` apart,
+    /// instead of being sent as a single key-down/key-up cycle.
+    pub repeat: Option<(usize, Duration)>,
 }
 
 impl Press {
@@ -36,6 +50,7 @@ impl Press {
             code: Code::UnicodeKey(code.into()),
             delay: None,
             duration: None,
+            repeat: None,
         }
     }
 
@@ -44,6 +59,7 @@ impl Press {
         match self.code {
             Code::UnicodeKey(_) => {}
             Code::VirtualKey(k) => self.code = Code::UnicodeKey(k),
+            Code::ScanCode(k) => self.code = Code::UnicodeKey(k),
         }
         self
     }
@@ -53,6 +69,24 @@ impl Press {
         match self.code {
             Code::UnicodeKey(k) => self.code = Code::VirtualKey(k),
             Code::VirtualKey(_) => {}
+            Code::ScanCode(k) => self.code = Code::VirtualKey(k),
+        }
+        self
+    }
+
+    /// Converts the press into a scancode keypress, bypassing layout
+    /// remapping on backends that support it.
+    ///
+    /// The held value is kept as-is and treated as a virtual-key code: the
+    /// backend resolves the actual hardware scancode for it at send time (on
+    /// Windows, via `MapVirtualKeyW`). This applies to a press built straight
+    /// from `Code::ScanCode` too, so its value is never sent as a raw
+    /// scancode.
+    pub fn as_scancode(mut self) -> Self {
+        match self.code {
+            Code::UnicodeKey(k) => self.code = Code::ScanCode(k),
+            Code::VirtualKey(k) => self.code = Code::ScanCode(k),
+            Code::ScanCode(_) => {}
         }
         self
     }
@@ -68,6 +102,17 @@ impl Press {
         self.duration = Some(duration);
         self
     }
+
+    /// Makes this press auto-repeat: instead of a single key-down/key-up
+    /// cycle, it is sent as `count` cycles spaced `interval` apart, the way
+    /// a real keyboard auto-repeats a held key.
+    ///
+    /// `delay` and `duration` still apply as normal, just per-cycle rather
+    /// than once for the whole press.
+    pub fn with_repeat(mut self, count: usize, interval: Duration) -> Self {
+        self.repeat = Some((count, interval));
+        self
+    }
 }
 
 /// Helper used when generating key presses from utf16-encoded strings
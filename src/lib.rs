@@ -37,6 +37,19 @@
 #[cfg(target_os = "windows")]
 mod win;
 
+/// Captures live keystrokes into a `Chord`, for record-and-replay
+#[cfg(target_os = "windows")]
+pub mod record;
+
+/// Support sending input events on linux, via a virtual `uinput` device
+#[cfg(target_os = "linux")]
+mod uinput;
+
+/// Support sending input events on Wayland, via a virtual keyboard with a
+/// synthesized keymap covering arbitrary Unicode
+#[cfg(target_os = "linux")]
+pub mod wayland;
+
 /// Provides a `Press` type, that respresents pressing a key for some duration.
 ///
 /// `Press` events are used to a sequence of key-down + key-up events when playing
@@ -48,6 +61,10 @@ pub use key::Press;
 pub mod codes;
 pub use codes::VirtualKey;
 
+/// Errors that can occur while playing back a `Chord`
+pub mod error;
+pub use error::DisplayError;
+
 use rand::Rng;
 
 /// A `Chord` is a group of key-presses that will be transmitted in-bulk to the system
@@ -81,12 +98,42 @@ impl Chord {
     }
 
     /// Push the keypresses required to write the string to the end of the chord
+    ///
+    /// This encodes every character as a raw UTF-16 `UnicodeKey` press,
+    /// including control characters like `\n` and `\t` - so a newline is
+    /// sent as the literal code unit `0x0A` rather than pressing Enter. Most
+    /// callers want [`Chord::type_str`] instead, which maps known control
+    /// characters onto the virtual key that actually produces them.
     pub fn push_str(&mut self, keys: &str) {
         for k in keys.encode_utf16() {
             self.keys.push(Press::from(k))
         }
     }
 
+    /// Push the keypresses required to type the string to the end of the
+    /// chord, the way a real keyboard would produce it.
+    ///
+    /// Known control characters are translated into the virtual key that
+    /// actually produces them (`\n`/`\r` -> Enter, `\t` -> Tab, `\x08` ->
+    /// Backspace, `\x1b` -> Escape, `\x7f` -> Delete) instead of being
+    /// injected as their raw code unit, which most applications don't treat
+    /// as the corresponding keypress. Every other character is pushed the
+    /// same way [`Chord::push_str`] would.
+    pub fn type_str(&mut self, keys: &str) {
+        let mut unit_buf = [0u16; 2];
+
+        for c in keys.chars() {
+            if let Some(vk) = control_virtual_key(c) {
+                self.keys.push(Press::from(vk));
+                continue;
+            }
+
+            for unit in c.encode_utf16(&mut unit_buf) {
+                self.keys.push(Press::from(*unit));
+            }
+        }
+    }
+
     pub fn typewriter(&mut self, range: std::ops::Range<u64>) {
         let mut rng = rand::rng();
 
@@ -102,14 +149,88 @@ impl Chord {
     }
 
     /// Playback the key events after some delay
-    pub async fn play_after(self, duration: std::time::Duration) {
+    pub async fn play_after(self, duration: std::time::Duration) -> Result<(), DisplayError> {
         tokio::time::sleep(duration).await;
         self.play().await
     }
 
     /// Playback the key events to the system
-    pub async fn play(&self) {
+    pub async fn play(&self) -> Result<(), DisplayError> {
         #[cfg(target_os = "windows")]
-        win::send_inputs(&self.keys).await
+        {
+            win::send_inputs(&self.keys).await;
+            Ok(())
+        }
+
+        #[cfg(target_os = "linux")]
+        uinput::send_inputs(&self.keys).await
+    }
+}
+
+/// Maps a control character onto the virtual key that produces it, for
+/// `Chord::type_str`.
+fn control_virtual_key(c: char) -> Option<VirtualKey> {
+    Some(match c {
+        '\n' | '\r' => VirtualKey::Enter,
+        '\t' => VirtualKey::Tab,
+        '\x08' => VirtualKey::Backspace,
+        '\x1b' => VirtualKey::Escape,
+        '\x7f' => VirtualKey::Delete,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Code;
+
+    #[test]
+    fn control_virtual_key_maps_known_control_characters() {
+        assert_eq!(control_virtual_key('\n'), Some(VirtualKey::Enter));
+        assert_eq!(control_virtual_key('\r'), Some(VirtualKey::Enter));
+        assert_eq!(control_virtual_key('\t'), Some(VirtualKey::Tab));
+        assert_eq!(control_virtual_key('\x08'), Some(VirtualKey::Backspace));
+        assert_eq!(control_virtual_key('\x1b'), Some(VirtualKey::Escape));
+        assert_eq!(control_virtual_key('\x7f'), Some(VirtualKey::Delete));
+    }
+
+    #[test]
+    fn control_virtual_key_ignores_printable_characters() {
+        assert_eq!(control_virtual_key('a'), None);
+        assert_eq!(control_virtual_key(' '), None);
+    }
+
+    #[test]
+    fn type_str_translates_newline_into_enter() {
+        let mut chord = Chord::new();
+        chord.type_str("a\n");
+
+        assert_eq!(chord.keys.len(), 2);
+        assert_eq!(
+            chord.keys[0].code,
+            Code::UnicodeKey('a'.encode_utf16(&mut [0u16; 2])[0])
+        );
+        assert_eq!(chord.keys[1].code, Code::VirtualKey(VirtualKey::Enter as u16));
+    }
+
+    #[test]
+    fn type_str_matches_push_str_for_printable_text() {
+        let mut typed = Chord::new();
+        typed.type_str("Hello");
+
+        let mut pushed = Chord::new();
+        pushed.push_str("Hello");
+
+        assert_eq!(typed.keys, pushed.keys);
+    }
+
+    #[test]
+    fn push_str_keeps_control_characters_as_raw_code_units() {
+        let mut chord = Chord::new();
+        chord.push_str("\n");
+
+        assert_eq!(chord.keys.len(), 1);
+        assert_eq!(chord.keys[0].code, Code::UnicodeKey(0x0A));
     }
 }
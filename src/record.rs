@@ -0,0 +1,218 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::KBDLLHOOKSTRUCT;
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, PeekMessageW, SetWindowsHookExW, UnhookWindowsHookEx, HHOOK, MSG, PM_REMOVE,
+    WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+use crate::win::INJECTED_SENTINEL;
+use crate::{Chord, Press};
+
+struct KeyEvent {
+    vk: u16,
+    down: bool,
+    at: Instant,
+}
+
+/// Events captured by `hook_proc`, drained by the recorder thread.
+///
+/// `SetWindowsHookExW` only accepts a plain function pointer, so the
+/// callback has no way to close over a `Recorder`'s state - this is the only
+/// channel it has back out.
+static EVENTS: OnceLock<Mutex<VecDeque<KeyEvent>>> = OnceLock::new();
+
+fn events() -> &'static Mutex<VecDeque<KeyEvent>> {
+    EVENTS.get_or_init(|| Mutex::new(VecDeque::new()))
+}
+
+unsafe extern "system" fn hook_proc(
+    code: i32,
+    wparam: windows_sys::Win32::Foundation::WPARAM,
+    lparam: windows_sys::Win32::Foundation::LPARAM,
+) -> windows_sys::Win32::Foundation::LRESULT {
+    if code >= 0 {
+        let info = &*(lparam as *const KBDLLHOOKSTRUCT);
+        let message = wparam as u32;
+        let is_down = message == WM_KEYDOWN || message == WM_SYSKEYDOWN;
+        let is_up = message == WM_KEYUP || message == WM_SYSKEYUP;
+
+        if (is_down || is_up) && info.dwExtraInfo != INJECTED_SENTINEL {
+            events().lock().unwrap().push_back(KeyEvent {
+                vk: info.vkCode as u16,
+                down: is_down,
+                at: Instant::now(),
+            });
+        }
+    }
+
+    CallNextHookEx(std::ptr::null_mut(), code, wparam, lparam)
+}
+
+/// Captures live keystrokes through a `WH_KEYBOARD_LL` hook and turns them
+/// into a `Chord`, so a user's input can be replayed exactly as typed -
+/// including its original timing.
+pub struct Recorder {
+    stop: Arc<AtomicBool>,
+    pump: JoinHandle<()>,
+}
+
+impl Recorder {
+    /// Installs the hook and starts pumping messages for it on a dedicated
+    /// thread.
+    pub fn start() -> Self {
+        events().lock().unwrap().clear();
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let pump_stop = stop.clone();
+
+        let pump = std::thread::spawn(move || unsafe {
+            let hook: HHOOK =
+                SetWindowsHookExW(WH_KEYBOARD_LL, Some(hook_proc), std::ptr::null_mut(), 0);
+
+            let mut msg: MSG = std::mem::zeroed();
+            while !pump_stop.load(Ordering::Relaxed) {
+                while PeekMessageW(&mut msg, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {}
+                std::thread::sleep(Duration::from_millis(5));
+            }
+
+            UnhookWindowsHookEx(hook);
+        });
+
+        Self { stop, pump }
+    }
+
+    /// Uninstalls the hook and converts whatever was captured into a
+    /// `Chord`.
+    pub fn stop(self) -> Chord {
+        self.stop.store(true, Ordering::Relaxed);
+        self.pump.join().expect("recorder pump thread panicked");
+
+        events_to_chord(events().lock().unwrap().drain(..).collect())
+    }
+}
+
+/// Pairs up key-down/key-up events into `Press`es, with `delay` set to the
+/// gap since the previous key's key-*down* (matching how `win::send_inputs`
+/// interprets `delay`: `clock` only advances by `delay` relative to the
+/// previous press's `down_at`, never by its `duration`, so that chorded
+/// holds can overlap on replay) and `duration` set to how long the key was
+/// actually held.
+///
+/// Windows fires repeated `WM_KEYDOWN` for OS auto-repeat while a key is
+/// held, so `down_at` only records the *first* key-down seen for a given
+/// virtual key - later repeats while it's still held are ignored, rather
+/// than overwriting it and truncating the recorded `duration`.
+fn events_to_chord(events: VecDeque<KeyEvent>) -> Chord {
+    let mut chord = Chord::new();
+    let mut down_at: HashMap<u16, Instant> = HashMap::new();
+    let mut last_down: Option<Instant> = None;
+
+    for event in events {
+        if event.down {
+            down_at.entry(event.vk).or_insert(event.at);
+            continue;
+        }
+
+        let Some(pressed_at) = down_at.remove(&event.vk) else {
+            continue;
+        };
+
+        let delay = last_down
+            .map(|prev| pressed_at.saturating_duration_since(prev))
+            .unwrap_or_default();
+
+        chord.push(
+            Press::new(event.vk)
+                .as_virtual()
+                .with_delay(delay)
+                .with_duration(event.at.saturating_duration_since(pressed_at)),
+        );
+
+        last_down = Some(pressed_at);
+    }
+
+    chord
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Code;
+
+    fn press_evt(vk: u16, down: bool, at: Instant) -> KeyEvent {
+        KeyEvent { vk, down, at }
+    }
+
+    #[test]
+    fn delay_is_measured_from_previous_key_down_not_previous_release() {
+        let t0 = Instant::now();
+
+        // Key A: down at 0ms, up at 80ms (held 80ms).
+        // Key B: down at 280ms, up at 300ms - i.e. 280ms after A's key-down.
+        let events = VecDeque::from([
+            press_evt(b'A' as u16, true, t0),
+            press_evt(b'A' as u16, false, t0 + Duration::from_millis(80)),
+            press_evt(b'B' as u16, true, t0 + Duration::from_millis(280)),
+            press_evt(b'B' as u16, false, t0 + Duration::from_millis(300)),
+        ]);
+
+        let chord = events_to_chord(events);
+
+        assert_eq!(chord.keys.len(), 2);
+        assert_eq!(chord.keys[0].delay, Some(Duration::ZERO));
+        assert_eq!(chord.keys[0].duration, Some(Duration::from_millis(80)));
+
+        // Matches win::send_inputs, whose `clock` advances by `delay`
+        // relative to the previous press's `down_at`, not its `up_at`.
+        assert_eq!(chord.keys[1].delay, Some(Duration::from_millis(280)));
+        assert_eq!(chord.keys[1].duration, Some(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn auto_repeat_key_downs_do_not_reset_the_original_press_instant() {
+        let t0 = Instant::now();
+
+        // Key held down generates repeated WM_KEYDOWN ticks before the
+        // eventual key-up; `duration` should span from the first tick, not
+        // the last one.
+        let events = VecDeque::from([
+            press_evt(b'A' as u16, true, t0),
+            press_evt(b'A' as u16, true, t0 + Duration::from_millis(30)),
+            press_evt(b'A' as u16, true, t0 + Duration::from_millis(60)),
+            press_evt(b'A' as u16, false, t0 + Duration::from_millis(90)),
+        ]);
+
+        let chord = events_to_chord(events);
+
+        assert_eq!(chord.keys.len(), 1);
+        assert_eq!(chord.keys[0].duration, Some(Duration::from_millis(90)));
+    }
+
+    #[test]
+    fn unmatched_key_up_is_ignored() {
+        let t0 = Instant::now();
+        let events = VecDeque::from([press_evt(b'A' as u16, false, t0)]);
+
+        let chord = events_to_chord(events);
+
+        assert!(chord.keys.is_empty());
+    }
+
+    #[test]
+    fn presses_are_tagged_as_virtual_keys() {
+        let t0 = Instant::now();
+        let events = VecDeque::from([
+            press_evt(0x0D, true, t0),
+            press_evt(0x0D, false, t0 + Duration::from_millis(10)),
+        ]);
+
+        let chord = events_to_chord(events);
+
+        assert_eq!(chord.keys[0].code, Code::VirtualKey(0x0D));
+    }
+}
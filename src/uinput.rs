@@ -0,0 +1,358 @@
+use std::time::Duration;
+
+use evdev::uinput::VirtualDeviceBuilder;
+use evdev::{AttributeSet, EventType, InputEvent, Key};
+
+use crate::error::DisplayError;
+use crate::key::{Code, Press};
+
+/// Looks up the evdev `Key` for one of our Win32-numbered `VirtualKey`s.
+///
+/// Only the keys that `codes::VirtualKey` currently defines are mapped; new
+/// virtual keys need an entry here too.
+fn virtual_key_to_evdev(vk: u16) -> Option<Key> {
+    Some(match vk {
+        0x08 => Key::KEY_BACKSPACE,
+        0x09 => Key::KEY_TAB,
+        0x0D => Key::KEY_ENTER,
+        0x10 => Key::KEY_LEFTSHIFT,
+        0x11 => Key::KEY_LEFTCTRL,
+        0x12 => Key::KEY_LEFTALT,
+        0x1B => Key::KEY_ESC,
+        0x20 => Key::KEY_SPACE,
+        0x21 => Key::KEY_PAGEUP,
+        0x22 => Key::KEY_PAGEDOWN,
+        0x23 => Key::KEY_END,
+        0x24 => Key::KEY_HOME,
+        0x25 => Key::KEY_LEFT,
+        0x26 => Key::KEY_UP,
+        0x27 => Key::KEY_RIGHT,
+        0x28 => Key::KEY_DOWN,
+        0x2E => Key::KEY_DELETE,
+        0xA0 => Key::KEY_LEFTSHIFT,
+        0xA1 => Key::KEY_RIGHTSHIFT,
+        0xA2 => Key::KEY_LEFTCTRL,
+        0xA3 => Key::KEY_RIGHTCTRL,
+        _ => return None,
+    })
+}
+
+/// Resolves a character to the `(Key, shift)` pair that produces it on a
+/// plain US QWERTY layout. This stands in for a full layout database; only
+/// the keys reachable with zero or one shift modifier are covered.
+fn char_to_evdev(c: char) -> Option<(Key, bool)> {
+    let lower = "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./ \t\n";
+    let upper = "~!@#$%^&*()_+QWERTYUIOP{}|ASDFGHJKL:\"ZXCVBNM<>? \t\n";
+    let keys = [
+        Key::KEY_GRAVE,
+        Key::KEY_1,
+        Key::KEY_2,
+        Key::KEY_3,
+        Key::KEY_4,
+        Key::KEY_5,
+        Key::KEY_6,
+        Key::KEY_7,
+        Key::KEY_8,
+        Key::KEY_9,
+        Key::KEY_0,
+        Key::KEY_MINUS,
+        Key::KEY_EQUAL,
+        Key::KEY_Q,
+        Key::KEY_W,
+        Key::KEY_E,
+        Key::KEY_R,
+        Key::KEY_T,
+        Key::KEY_Y,
+        Key::KEY_U,
+        Key::KEY_I,
+        Key::KEY_O,
+        Key::KEY_P,
+        Key::KEY_LEFTBRACE,
+        Key::KEY_RIGHTBRACE,
+        Key::KEY_BACKSLASH,
+        Key::KEY_A,
+        Key::KEY_S,
+        Key::KEY_D,
+        Key::KEY_F,
+        Key::KEY_G,
+        Key::KEY_H,
+        Key::KEY_J,
+        Key::KEY_K,
+        Key::KEY_L,
+        Key::KEY_SEMICOLON,
+        Key::KEY_APOSTROPHE,
+        Key::KEY_Z,
+        Key::KEY_X,
+        Key::KEY_C,
+        Key::KEY_V,
+        Key::KEY_B,
+        Key::KEY_N,
+        Key::KEY_M,
+        Key::KEY_COMMA,
+        Key::KEY_DOT,
+        Key::KEY_SLASH,
+        Key::KEY_SPACE,
+        Key::KEY_TAB,
+        Key::KEY_ENTER,
+    ];
+
+    if let Some(idx) = lower.chars().position(|ch| ch == c) {
+        return Some((keys[idx], false));
+    }
+    if let Some(idx) = upper.chars().position(|ch| ch == c) {
+        return Some((keys[idx], true));
+    }
+    None
+}
+
+/// Resolves a single `Press` into the evdev key it drives, plus whether a
+/// shift modifier must be held alongside it.
+fn resolve(press: &Press) -> Result<(Key, bool), DisplayError> {
+    match &press.code {
+        Code::VirtualKey(vk) => Ok((
+            virtual_key_to_evdev(*vk).ok_or(DisplayError::UnsupportedVirtualKey(*vk))?,
+            false,
+        )),
+        Code::UnicodeKey(unit) => {
+            let c = char::from_u32(*unit as u32).ok_or(DisplayError::InvalidCodeUnit(*unit))?;
+            char_to_evdev(c).ok_or(DisplayError::UnsupportedChar(c))
+        }
+        // `ScanCode` exists to bypass Windows' virtual-key/unicode layer;
+        // evdev already drives the device at the keycode level, so it's
+        // resolved the same way a virtual key is.
+        Code::ScanCode(vk) => Ok((
+            virtual_key_to_evdev(*vk).ok_or(DisplayError::UnsupportedVirtualKey(*vk))?,
+            false,
+        )),
+    }
+}
+
+/// One scheduled key-down or key-up, at the offset (from the start of
+/// playback) it should be emitted at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ScheduledEvent {
+    at: Duration,
+    key: Key,
+    down: bool,
+}
+
+/// Expands a single resolved press into its key-down/key-up events
+/// (including `repeat` cycles), and returns the clock position the next
+/// press's `delay` should stack onto.
+///
+/// Pulled out of `send_inputs` so the scheduling math can be tested without
+/// a real uinput device.
+fn schedule_one(press: &Press, key: Key, shift: bool, clock: Duration) -> (Vec<ScheduledEvent>, Duration) {
+    let mut events = Vec::new();
+
+    let (count, interval) = press.repeat.unwrap_or((1, Duration::ZERO));
+    let hold = press.duration.unwrap_or_default();
+    let mut down_at = clock;
+
+    for cycle in 0..count {
+        let up_at = down_at + hold;
+
+        if shift {
+            events.push(ScheduledEvent {
+                at: down_at,
+                key: Key::KEY_LEFTSHIFT,
+                down: true,
+            });
+        }
+        events.push(ScheduledEvent {
+            at: down_at,
+            key,
+            down: true,
+        });
+        events.push(ScheduledEvent {
+            at: up_at,
+            key,
+            down: false,
+        });
+        if shift {
+            events.push(ScheduledEvent {
+                at: up_at,
+                key: Key::KEY_LEFTSHIFT,
+                down: false,
+            });
+        }
+
+        if cycle + 1 < count {
+            down_at = up_at + interval + press.delay.unwrap_or_default();
+        }
+    }
+
+    // Leave the clock at the final cycle's key-down, matching the
+    // non-repeating case, so the next press's `delay` stacks the same way
+    // it always has (relative to this press's key-down, allowing chorded
+    // presses to overlap).
+    (events, down_at)
+}
+
+/// Plays a chord's worth of key presses to the system through a virtual
+/// `uinput` device.
+///
+/// Key-downs are always emitted before any key-up scheduled for the same
+/// instant, so overlapping presses in a chord are held down simultaneously
+/// rather than being released early.
+pub async fn send_inputs(presses: &[Press]) -> Result<(), DisplayError> {
+    let mut keys = AttributeSet::<Key>::new();
+    let mut resolved = Vec::with_capacity(presses.len());
+
+    for press in presses {
+        let (key, shift) = resolve(press)?;
+        keys.insert(key);
+        if shift {
+            keys.insert(Key::KEY_LEFTSHIFT);
+        }
+        resolved.push((press, key, shift));
+    }
+
+    let mut device = VirtualDeviceBuilder::new()
+        .map_err(|e| DisplayError::Device(e.to_string()))?
+        .name("keyboard-chords")
+        .with_keys(&keys)
+        .map_err(|e| DisplayError::Device(e.to_string()))?
+        .build()
+        .map_err(|e| DisplayError::Device(e.to_string()))?;
+
+    let mut schedule = Vec::with_capacity(resolved.len() * 3);
+    let mut clock = Duration::ZERO;
+
+    for (press, key, shift) in resolved {
+        clock += press.delay.unwrap_or_default();
+
+        // Repeats are expanded here, at send time, so `delay`/`duration`
+        // still apply per cycle rather than once for the whole press.
+        let (events, new_clock) = schedule_one(press, key, shift, clock);
+        schedule.extend(events);
+        clock = new_clock;
+    }
+
+    schedule.sort_by(|a, b| a.at.cmp(&b.at).then(a.down.cmp(&b.down).reverse()));
+
+    let mut elapsed = Duration::ZERO;
+    let mut i = 0;
+    while i < schedule.len() {
+        let at = schedule[i].at;
+        if at > elapsed {
+            tokio::time::sleep(at - elapsed).await;
+            elapsed = at;
+        }
+
+        let mut group = Vec::new();
+        while i < schedule.len() && schedule[i].at == at {
+            let ev = &schedule[i];
+            group.push(InputEvent::new(
+                EventType::KEY.0,
+                ev.key.code(),
+                ev.down as i32,
+            ));
+            i += 1;
+        }
+        device
+            .emit(&group)
+            .map_err(|e| DisplayError::Device(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_to_evdev_maps_lowercase_and_uppercase() {
+        assert_eq!(char_to_evdev('a'), Some((Key::KEY_A, false)));
+        assert_eq!(char_to_evdev('A'), Some((Key::KEY_A, true)));
+        assert_eq!(char_to_evdev('1'), Some((Key::KEY_1, false)));
+        assert_eq!(char_to_evdev('!'), Some((Key::KEY_1, true)));
+    }
+
+    #[test]
+    fn char_to_evdev_rejects_unmapped_chars() {
+        assert_eq!(char_to_evdev('€'), None);
+    }
+
+    #[test]
+    fn virtual_key_to_evdev_maps_known_keys() {
+        assert_eq!(virtual_key_to_evdev(0x0D), Some(Key::KEY_ENTER));
+        assert_eq!(virtual_key_to_evdev(0x08), Some(Key::KEY_BACKSPACE));
+    }
+
+    #[test]
+    fn virtual_key_to_evdev_rejects_unknown_vk() {
+        assert_eq!(virtual_key_to_evdev(0xFFFF), None);
+    }
+
+    #[test]
+    fn resolve_reports_unsupported_virtual_key() {
+        let press = Press::new(0xFFFF_u16).as_virtual();
+        assert_eq!(
+            resolve(&press),
+            Err(DisplayError::UnsupportedVirtualKey(0xFFFF))
+        );
+    }
+
+    #[test]
+    fn resolve_reports_invalid_code_unit() {
+        // A lone UTF-16 surrogate half doesn't decode to a `char` on its own.
+        let press = Press::new(0xD800_u16);
+        assert_eq!(resolve(&press), Err(DisplayError::InvalidCodeUnit(0xD800)));
+    }
+
+    #[test]
+    fn schedule_one_expands_repeat_into_separate_cycles() {
+        let press = Press::new(0x0041_u16)
+            .with_duration(Duration::from_millis(10))
+            .with_repeat(3, Duration::from_millis(20));
+
+        let (events, next_clock) = schedule_one(&press, Key::KEY_A, false, Duration::ZERO);
+
+        // 3 cycles * (down + up) = 6 events.
+        assert_eq!(events.len(), 6);
+
+        let downs: Vec<Duration> = events.iter().filter(|e| e.down).map(|e| e.at).collect();
+        assert_eq!(
+            downs,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(30),
+                Duration::from_millis(60),
+            ]
+        );
+
+        // The clock is left at the final cycle's key-down.
+        assert_eq!(next_clock, Duration::from_millis(60));
+    }
+
+    #[test]
+    fn schedule_one_includes_shift_events_for_uppercase() {
+        let press = Press::new(0x0041_u16);
+        let (events, _) = schedule_one(&press, Key::KEY_A, true, Duration::ZERO);
+
+        assert!(events.iter().any(|e| e.key == Key::KEY_LEFTSHIFT && e.down));
+        assert!(events.iter().any(|e| e.key == Key::KEY_LEFTSHIFT && !e.down));
+    }
+
+    #[test]
+    fn schedule_sorts_downs_before_ups_at_the_same_instant() {
+        // Two presses with no delay/duration land their down and up at the
+        // exact same instant - downs must still come first so the keys
+        // overlap instead of one releasing before the other goes down.
+        let (mut a, _) = schedule_one(
+            &Press::new(0x0041_u16),
+            Key::KEY_A,
+            false,
+            Duration::ZERO,
+        );
+        let (b, _) = schedule_one(&Press::new(0x0042_u16), Key::KEY_B, false, Duration::ZERO);
+        a.extend(b);
+
+        a.sort_by(|x, y| x.at.cmp(&y.at).then(x.down.cmp(&y.down).reverse()));
+
+        assert!(a[0].down && a[1].down, "both key-downs should sort first");
+        assert!(!a[2].down && !a[3].down);
+    }
+}
@@ -0,0 +1,484 @@
+//! A Wayland backend for `Chord`, via `zwp_virtual_keyboard_v1`.
+//!
+//! Unlike `win`/`uinput`, this backend can't be driven through `Chord::play`
+//! directly: typing arbitrary Unicode means maintaining a live connection and
+//! a keycode assignment table across calls, rather than opening a throwaway
+//! device per chord. Construct a [`Keymap`] once and reuse it:
+//!
+//! ```no_run
+//! # use keyboard_chords::{wayland::Keymap, Chord};
+//! # #[tokio::main]
+//! # async fn main() {
+//! let mut keymap = Keymap::connect().expect("failed to connect to compositor");
+//!
+//! let mut chord = Chord::new();
+//! chord.push_str("こんにちは");
+//!
+//! keymap.play(&chord.keys).await.expect("failed to play chord");
+//! # }
+//! ```
+use std::collections::HashMap;
+use std::io::{Seek, SeekFrom, Write};
+use std::os::fd::AsFd;
+use std::time::{Duration, Instant};
+
+use wayland_client::globals::{registry_queue_init, GlobalListContents};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::protocol::wl_seat::WlSeat;
+use wayland_client::{delegate_noop, Connection, Dispatch, EventQueue, QueueHandle};
+use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1;
+use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1;
+
+use crate::error::DisplayError;
+use crate::key::{Code, Press};
+
+/// First keycode we hand out. XKB reserves 0-7, and keycodes below 8 are
+/// treated specially by most compositors, so evdev-style keymaps
+/// conventionally start assigning at 8.
+const FIRST_KEYCODE: u32 = 8;
+
+/// One past the last keycode we'll ever hand out. Equal to the (inclusive)
+/// `maximum` declared in `render_keymap`'s `xkb_keycodes` section, so that
+/// keycode itself is never assigned. Bounds a `Keymap` to
+/// `KEYCODE_LIMIT - FIRST_KEYCODE` (247) symbols alive at once.
+const KEYCODE_LIMIT: u32 = 255;
+
+struct State;
+
+/// The registry's own events are already captured into the `GlobalList`
+/// `registry_queue_init` returns, so there's nothing left for this impl to
+/// do - but `Dispatch<WlRegistry, GlobalListContents>` still has to exist
+/// for `registry_queue_init::<State>` to type-check.
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as wayland_client::Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+// None of these objects' events matter to us (a seat's capabilities, or a
+// virtual keyboard's - of which `zwp_virtual_keyboard_v1` sends none), so
+// they're dispatched as no-ops.
+delegate_noop!(State: ignore WlSeat);
+delegate_noop!(State: ignore ZwpVirtualKeyboardManagerV1);
+delegate_noop!(State: ignore ZwpVirtualKeyboardV1);
+
+/// Assigns and reclaims keycodes for symbols, independently of anything
+/// Wayland-specific, so the assignment/reclamation logic can be unit tested
+/// without a live connection.
+#[derive(Default)]
+struct SymbolTable {
+    /// symbol -> (keycode, outstanding reference count)
+    symbols: HashMap<char, (u32, usize)>,
+    /// Keycodes `release` has freed (their last reference hit zero),
+    /// available for `reserve` to hand back out before minting a new one.
+    free_keycodes: Vec<u32>,
+    /// One past the highest keycode ever minted (not reused via
+    /// `free_keycodes`); only grows.
+    next_keycode: u32,
+}
+
+impl SymbolTable {
+    fn new() -> Self {
+        Self {
+            symbols: HashMap::new(),
+            free_keycodes: Vec::new(),
+            next_keycode: FIRST_KEYCODE,
+        }
+    }
+
+    /// Assigns `c` a keycode if it doesn't have one yet (reusing a freed one
+    /// where possible), or bumps its reference count if it does. Returns
+    /// whether a new keycode was assigned, i.e. whether the keymap needs
+    /// regenerating.
+    fn reserve(&mut self, c: char) -> Result<bool, DisplayError> {
+        if let Some((_, refs)) = self.symbols.get_mut(&c) {
+            *refs += 1;
+            return Ok(false);
+        }
+
+        let keycode = if let Some(keycode) = self.free_keycodes.pop() {
+            keycode
+        } else if self.next_keycode < KEYCODE_LIMIT {
+            let keycode = self.next_keycode;
+            self.next_keycode += 1;
+            keycode
+        } else {
+            return Err(DisplayError::CapacityExceeded(format!(
+                "keymap cannot hold more than {} live symbols at once",
+                KEYCODE_LIMIT - FIRST_KEYCODE
+            )));
+        };
+
+        self.symbols.insert(c, (keycode, 1));
+        Ok(true)
+    }
+
+    /// Drops a reference to `c`, freeing its keycode for reuse once nothing
+    /// references it anymore. Returns whether it was just freed, i.e.
+    /// whether the keymap needs regenerating.
+    fn release(&mut self, c: char) -> bool {
+        let Some((keycode, refs)) = self.symbols.get_mut(&c) else {
+            return false;
+        };
+
+        *refs = refs.saturating_sub(1);
+        if *refs == 0 {
+            let keycode = *keycode;
+            self.symbols.remove(&c);
+            self.free_keycodes.push(keycode);
+            return true;
+        }
+        false
+    }
+
+    fn keycode(&self, c: char) -> Option<u32> {
+        self.symbols.get(&c).map(|(keycode, _)| *keycode)
+    }
+}
+
+/// Maintains a Wayland virtual keyboard whose keymap is synthesized on the
+/// fly, so any `char` that appears in a `Chord` can be typed regardless of
+/// what layout the compositor is actually configured with.
+///
+/// Mirrors the approach hid-io's `Keymap` takes: every unique symbol gets its
+/// own keycode, the keymap is a flat (single-level, unshifted) mapping from
+/// keycode to keysym, and it's only regenerated when the set of symbols
+/// actually changes.
+pub struct Keymap {
+    _conn: Connection,
+    queue: EventQueue<State>,
+    keyboard: ZwpVirtualKeyboardV1,
+    table: SymbolTable,
+    dirty: bool,
+}
+
+impl Keymap {
+    /// Connects to the compositor and creates a virtual keyboard bound to
+    /// the default seat.
+    pub fn connect() -> Result<Self, DisplayError> {
+        let conn =
+            Connection::connect_to_env().map_err(|e| DisplayError::Connection(e.to_string()))?;
+
+        let (globals, mut queue) = registry_queue_init::<State>(&conn)
+            .map_err(|e| DisplayError::Connection(e.to_string()))?;
+        let qh = queue.handle();
+
+        let seat: WlSeat = globals
+            .bind(&qh, 1..=1, ())
+            .map_err(|e| DisplayError::Connection(e.to_string()))?;
+        let manager: ZwpVirtualKeyboardManagerV1 = globals
+            .bind(&qh, 1..=1, ())
+            .map_err(|e| DisplayError::Connection(e.to_string()))?;
+        let keyboard = manager.create_virtual_keyboard(&seat, &qh, ());
+
+        queue
+            .roundtrip(&mut State)
+            .map_err(|e| DisplayError::Connection(e.to_string()))?;
+
+        Ok(Self {
+            _conn: conn,
+            queue,
+            keyboard,
+            table: SymbolTable::new(),
+            dirty: false,
+        })
+    }
+
+    /// Registers every symbol needed by `presses`, assigning fresh keycodes
+    /// to any that haven't been seen before (reusing one `release` freed up,
+    /// if one's available) and bumping the reference count of ones that have.
+    fn reserve(&mut self, presses: &[Press]) -> Result<(), DisplayError> {
+        for press in presses {
+            let c = press_char(press)?;
+            if self.table.reserve(c)? {
+                self.dirty = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Regenerates and uploads the keymap if the symbol set has changed
+    /// since the last upload.
+    fn sync_keymap(&mut self) -> Result<(), DisplayError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let keymap_string = render_keymap(&self.table.symbols)?;
+        let mut file = tempfile::tempfile().map_err(|e| DisplayError::Io(e.to_string()))?;
+        file.write_all(keymap_string.as_bytes())
+            .map_err(|e| DisplayError::Io(e.to_string()))?;
+        file.flush().map_err(|e| DisplayError::Io(e.to_string()))?;
+        file.seek(SeekFrom::Start(0))
+            .map_err(|e| DisplayError::Io(e.to_string()))?;
+
+        self.keyboard.keymap(
+            wayland_client::protocol::wl_keyboard::KeymapFormat::XkbV1.into(),
+            file.as_fd(),
+            keymap_string.len() as u32,
+        );
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Plays a chord through the virtual keyboard, assigning and uploading
+    /// any keycodes it needs first.
+    ///
+    /// Like the other backends, presses are laid out on an absolute schedule
+    /// (`clock` advances only by `delay`, never `duration`) so overlapping
+    /// holds in the same `Chord` stay simultaneous, and the real `delay`/
+    /// `duration`/repeat `interval` gaps are actually slept through rather
+    /// than sent back-to-back.
+    pub async fn play(&mut self, presses: &[Press]) -> Result<(), DisplayError> {
+        self.reserve(presses)?;
+        self.sync_keymap()?;
+
+        struct Scheduled {
+            at: Duration,
+            code: u32,
+            down: bool,
+        }
+
+        let mut schedule = Vec::with_capacity(presses.len() * 2);
+        let mut clock = Duration::ZERO;
+
+        for press in presses {
+            let c = press_char(press)?;
+            let keycode = self
+                .table
+                .keycode(c)
+                .expect("reserve() assigns every symbol play() will look up");
+            let evdev_code = keycode - FIRST_KEYCODE;
+
+            clock += press.delay.unwrap_or_default();
+            let (count, interval) = press.repeat.unwrap_or((1, Duration::ZERO));
+            let hold = press.duration.unwrap_or_default();
+            let mut down_at = clock;
+
+            for cycle in 0..count {
+                let up_at = down_at + hold;
+
+                schedule.push(Scheduled {
+                    at: down_at,
+                    code: evdev_code,
+                    down: true,
+                });
+                schedule.push(Scheduled {
+                    at: up_at,
+                    code: evdev_code,
+                    down: false,
+                });
+
+                if cycle + 1 < count {
+                    down_at = up_at + interval + press.delay.unwrap_or_default();
+                }
+            }
+
+            clock = down_at;
+        }
+
+        schedule.sort_by(|a, b| a.at.cmp(&b.at).then(a.down.cmp(&b.down).reverse()));
+
+        let start = Instant::now();
+        let mut elapsed = Duration::ZERO;
+        let mut i = 0;
+
+        while i < schedule.len() {
+            let at = schedule[i].at;
+            if at > elapsed {
+                tokio::time::sleep(at - elapsed).await;
+                elapsed = at;
+            }
+
+            while i < schedule.len() && schedule[i].at == at {
+                let ev = &schedule[i];
+                let time = start.elapsed().as_millis() as u32;
+                self.keyboard.key(time, ev.code, ev.down as u32);
+                i += 1;
+            }
+
+            self.queue
+                .flush()
+                .map_err(|e| DisplayError::Connection(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Releases a chord's symbols. Once a symbol's reference count hits
+    /// zero it's dropped from the keymap entirely and its keycode is handed
+    /// back to `reserve` to reuse for the next unseen symbol, keeping a
+    /// long-lived `Keymap` within the 247 keycodes it hands out.
+    pub fn release(&mut self, presses: &[Press]) {
+        for press in presses {
+            let Ok(c) = press_char(press) else { continue };
+            if self.table.release(c) {
+                self.dirty = true;
+            }
+        }
+    }
+}
+
+/// Extracts the `char` a `Press` represents. Only `Code::UnicodeKey` is
+/// meaningful here: the synthesized keymap holds nothing but the chord's own
+/// symbols, so virtual-key and scancode presses are rejected with
+/// `DisplayError::UnsupportedVirtualKey`.
+fn press_char(press: &Press) -> Result<char, DisplayError> {
+    match press.code {
+        Code::UnicodeKey(unit) => {
+            char::from_u32(unit as u32).ok_or(DisplayError::InvalidCodeUnit(unit))
+        }
+        // Virtual-key and scancode presses exist to hit a specific physical
+        // key rather than a character, so they fall outside what the
+        // synthesized keymap can represent.
+        Code::VirtualKey(vk) | Code::ScanCode(vk) => Err(DisplayError::UnsupportedVirtualKey(vk)),
+    }
+}
+
+/// Looks up the XKB keysym name for a character, special-casing the control
+/// characters that have dedicated keys rather than printable glyphs.
+fn keysym_for_char(c: char) -> Option<String> {
+    Some(match c {
+        '\n' => "Return".to_string(),
+        '\t' => "Tab".to_string(),
+        _ => xkbcommon::xkb::utf32_to_keysym(c as u32)
+            .map(|sym| xkbcommon::xkb::keysym_get_name(sym))?,
+    })
+}
+
+/// Builds a single-level XKB keymap that binds every assigned keycode
+/// directly to the keysym for its symbol, so no modifier is needed to reach
+/// it.
+///
+/// Free function (rather than a `Keymap` method) so it can be unit tested
+/// without a live Wayland connection.
+fn render_keymap(symbols: &HashMap<char, (u32, usize)>) -> Result<String, DisplayError> {
+    let mut keycodes = String::new();
+    let mut keys = String::new();
+    for (c, (keycode, _)) in symbols {
+        let keysym = keysym_for_char(*c).ok_or(DisplayError::UnsupportedChar(*c))?;
+        keycodes.push_str(&format!("    <K{keycode}> = {keycode};\n"));
+        keys.push_str(&format!("    key <K{keycode}> {{ [ {keysym} ] }};\n",));
+    }
+
+    Ok(format!(
+        "xkb_keymap {{\n\
+         xkb_keycodes \"(unnamed)\" {{\n    minimum = 8;\n    maximum = 255;\n{keycodes}}};\n\
+         xkb_types \"(unnamed)\" {{ }};\n\
+         xkb_compat \"(unnamed)\" {{ }};\n\
+         xkb_symbols \"(unnamed)\" {{\n{keys}}};\n\
+         }};\n"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keysym_for_char_special_cases_control_characters() {
+        assert_eq!(keysym_for_char('\n').as_deref(), Some("Return"));
+        assert_eq!(keysym_for_char('\t').as_deref(), Some("Tab"));
+    }
+
+    #[test]
+    fn keysym_for_char_looks_up_printable_characters() {
+        assert_eq!(keysym_for_char('a').as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn render_keymap_binds_each_keycode_to_its_keysym() {
+        let mut symbols = HashMap::new();
+        symbols.insert('a', (8, 1));
+        symbols.insert('\n', (9, 2));
+
+        let keymap = render_keymap(&symbols).expect("known symbols should render");
+
+        assert!(keymap.contains("key <K8> { [ a ] };"));
+        assert!(keymap.contains("key <K9> { [ Return ] };"));
+    }
+
+    #[test]
+    fn press_char_rejects_virtual_keys_and_scancodes() {
+        let virtual_press = Press::new(0x0D_u16).as_virtual();
+        assert_eq!(
+            press_char(&virtual_press),
+            Err(DisplayError::UnsupportedVirtualKey(0x0D))
+        );
+
+        let scancode_press = Press::new(0x0D_u16).as_scancode();
+        assert_eq!(
+            press_char(&scancode_press),
+            Err(DisplayError::UnsupportedVirtualKey(0x0D))
+        );
+    }
+
+    #[test]
+    fn press_char_extracts_unicode_characters() {
+        let press = Press::from('a' as u16);
+        assert_eq!(press_char(&press), Ok('a'));
+    }
+
+    #[test]
+    fn reserve_assigns_increasing_keycodes_and_bumps_refcounts() {
+        let mut table = SymbolTable::new();
+
+        assert_eq!(table.reserve('a'), Ok(true));
+        assert_eq!(table.reserve('b'), Ok(true));
+        assert_eq!(table.reserve('a'), Ok(false));
+
+        assert_eq!(table.keycode('a'), Some(FIRST_KEYCODE));
+        assert_eq!(table.keycode('b'), Some(FIRST_KEYCODE + 1));
+        assert_eq!(table.symbols[&'a'].1, 2);
+    }
+
+    #[test]
+    fn release_frees_the_keycode_once_refcount_hits_zero() {
+        let mut table = SymbolTable::new();
+        table.reserve('a').unwrap();
+        table.reserve('a').unwrap();
+
+        assert!(!table.release('a'));
+        assert_eq!(table.keycode('a'), Some(FIRST_KEYCODE));
+
+        assert!(table.release('a'));
+        assert_eq!(table.keycode('a'), None);
+    }
+
+    #[test]
+    fn reserve_reuses_a_freed_keycode_before_minting_a_new_one() {
+        let mut table = SymbolTable::new();
+        table.reserve('a').unwrap();
+        table.reserve('b').unwrap();
+        table.release('a');
+
+        assert_eq!(table.reserve('c'), Ok(true));
+        assert_eq!(table.keycode('c'), Some(FIRST_KEYCODE));
+    }
+
+    #[test]
+    fn reserve_reports_capacity_exceeded_instead_of_assigning_out_of_range_keycodes() {
+        let mut table = SymbolTable::new();
+        for i in 0..(KEYCODE_LIMIT - FIRST_KEYCODE) {
+            table
+                .reserve(char::from_u32('a' as u32 + i).unwrap())
+                .expect("within capacity");
+        }
+
+        let err = table.reserve('\u{2603}').unwrap_err();
+        assert_eq!(
+            err,
+            DisplayError::CapacityExceeded(format!(
+                "keymap cannot hold more than {} live symbols at once",
+                KEYCODE_LIMIT - FIRST_KEYCODE
+            ))
+        );
+    }
+}
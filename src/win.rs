@@ -0,0 +1,257 @@
+use crate::key::{Code, Press};
+use std::mem::size_of;
+use std::time::Duration;
+
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+    MapVirtualKeyW, SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_EXTENDEDKEY,
+    KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE, MAPVK_VK_TO_VSC,
+};
+
+/// Marks `INPUT` events this crate generates, via `KEYBDINPUT::dwExtraInfo`,
+/// so `record::Recorder` can tell them apart from real keystrokes and avoid
+/// feeding its own output back into itself.
+pub(crate) const INJECTED_SENTINEL: usize = 0x4348_524F; // "CHRO"
+
+/// Virtual-key values that Windows reports as "extended" keys (the
+/// duplicated keys on the right/numpad side of the keyboard, and the arrow
+/// cluster), which need `KEYEVENTF_EXTENDEDKEY` set alongside
+/// `KEYEVENTF_SCANCODE` or they're misinterpreted as their non-extended
+/// counterpart.
+fn is_extended_key(vk: u16) -> bool {
+    matches!(
+        vk,
+        0x21 /* PageUp */ | 0x22 /* PageDown */ | 0x23 /* End */ | 0x24 /* Home */
+        | 0x25 /* Left */ | 0x26 /* Up */ | 0x27 /* Right */ | 0x28 /* Down */
+        | 0x2E /* Delete */ | 0xA3 /* RightControl */
+    )
+}
+
+/// Builds a single `INPUT` record for a key-down or key-up event.
+fn keybd_input(code: &Code, key_up: bool) -> INPUT {
+    let (vk, scan, mut flags) = match code {
+        Code::VirtualKey(vk) => (*vk, 0, 0),
+        Code::UnicodeKey(unit) => (0, *unit, KEYEVENTF_UNICODE),
+        Code::ScanCode(vk) => {
+            let scan = unsafe { MapVirtualKeyW(*vk as u32, MAPVK_VK_TO_VSC) } as u16;
+            let extended = if is_extended_key(*vk) {
+                KEYEVENTF_EXTENDEDKEY
+            } else {
+                0
+            };
+            (0, scan, KEYEVENTF_SCANCODE | extended)
+        }
+    };
+
+    if key_up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: INJECTED_SENTINEL,
+            },
+        },
+    }
+}
+
+/// Sends a group of `INPUT` records to the system via a single `SendInput`
+/// call, so they land atomically with respect to other input sources.
+fn send_group(inputs: &[INPUT]) {
+    if inputs.is_empty() {
+        return;
+    }
+
+    unsafe {
+        SendInput(
+            inputs.len() as u32,
+            inputs.as_ptr(),
+            size_of::<INPUT>() as i32,
+        );
+    }
+}
+
+/// One scheduled key-down or key-up, at the offset (from the start of
+/// playback) it should be emitted at.
+#[derive(Debug, Clone, PartialEq)]
+struct ScheduledEvent {
+    at: Duration,
+    code: Code,
+    key_up: bool,
+}
+
+/// Expands a single press into its key-down/key-up events (including
+/// `repeat` cycles), and returns the clock position the next press's `delay`
+/// should stack onto.
+///
+/// Pulled out of `send_inputs` so the scheduling math can be tested without
+/// actually sending input.
+fn schedule_one(press: &Press, clock: Duration) -> (Vec<ScheduledEvent>, Duration) {
+    let mut events = Vec::new();
+
+    let (count, interval) = press.repeat.unwrap_or((1, Duration::ZERO));
+    let hold = press.duration.unwrap_or_default();
+    let mut down_at = clock;
+
+    for cycle in 0..count {
+        let up_at = down_at + hold;
+
+        events.push(ScheduledEvent {
+            at: down_at,
+            code: press.code.clone(),
+            key_up: false,
+        });
+        events.push(ScheduledEvent {
+            at: up_at,
+            code: press.code.clone(),
+            key_up: true,
+        });
+
+        if cycle + 1 < count {
+            down_at = up_at + interval + press.delay.unwrap_or_default();
+        }
+    }
+
+    // Leave the clock at the final cycle's key-down, matching the
+    // non-repeating case, so the next press's `delay` stacks the same way
+    // it always has (relative to this press's key-down, allowing chorded
+    // presses to overlap).
+    (events, down_at)
+}
+
+/// Plays a chord's worth of key presses to the system using the Win32 `SendInput` API.
+///
+/// Presses are laid out on an absolute schedule rather than sent one at a
+/// time: `clock` only advances by `delay`, never by `duration`, so two
+/// presses in the same `Chord` (one with no delay) are held down at the same
+/// time instead of being serialized - this is what lets a `Chord` press, say,
+/// 'UP' and 'RIGHT' simultaneously while holding 'UP' for longer. A press
+/// with `repeat` set is expanded into multiple key-down/key-up cycles here,
+/// at send time, rather than at the point it was pushed onto the `Chord`, so
+/// `delay` and `duration` still apply per cycle.
+pub async fn send_inputs(presses: &[Press]) {
+    let mut schedule = Vec::with_capacity(presses.len() * 2);
+    let mut clock = Duration::ZERO;
+
+    for press in presses {
+        clock += press.delay.unwrap_or_default();
+
+        // Repeats are expanded here, at send time, so `delay`/`duration`
+        // still apply per cycle rather than once for the whole press.
+        let (events, new_clock) = schedule_one(press, clock);
+        schedule.extend(events);
+        clock = new_clock;
+    }
+
+    // Key-downs sort before key-ups scheduled for the same instant, so
+    // overlapping presses are never released before the later one goes down.
+    schedule.sort_by(|a, b| a.at.cmp(&b.at).then(a.key_up.cmp(&b.key_up)));
+
+    let mut elapsed = Duration::ZERO;
+    let mut i = 0;
+    while i < schedule.len() {
+        let at = schedule[i].at;
+        if at > elapsed {
+            tokio::time::sleep(at - elapsed).await;
+            elapsed = at;
+        }
+
+        let mut group = Vec::new();
+        while i < schedule.len() && schedule[i].at == at {
+            group.push(keybd_input(&schedule[i].code, schedule[i].key_up));
+            i += 1;
+        }
+        send_group(&group);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_extended_key_identifies_nav_and_arrow_keys() {
+        assert!(is_extended_key(0x26)); // Up
+        assert!(is_extended_key(0x2E)); // Delete
+        assert!(!is_extended_key(0x41)); // 'A'
+        assert!(!is_extended_key(0x0D)); // Enter
+    }
+
+    #[test]
+    fn keybd_input_sets_scancode_and_extended_flags_for_scancode_presses() {
+        let extended = keybd_input(&Code::ScanCode(0x26), false); // Up
+        let flags = unsafe { extended.Anonymous.ki.dwFlags };
+        assert_eq!(flags & KEYEVENTF_SCANCODE, KEYEVENTF_SCANCODE);
+        assert_eq!(flags & KEYEVENTF_EXTENDEDKEY, KEYEVENTF_EXTENDEDKEY);
+
+        let plain = keybd_input(&Code::ScanCode(0x41), false); // 'A'
+        let flags = unsafe { plain.Anonymous.ki.dwFlags };
+        assert_eq!(flags & KEYEVENTF_SCANCODE, KEYEVENTF_SCANCODE);
+        assert_eq!(flags & KEYEVENTF_EXTENDEDKEY, 0);
+    }
+
+    #[test]
+    fn keybd_input_sets_keyup_flag_only_on_release() {
+        let down = keybd_input(&Code::VirtualKey(0x41), false);
+        assert_eq!(unsafe { down.Anonymous.ki.dwFlags } & KEYEVENTF_KEYUP, 0);
+
+        let up = keybd_input(&Code::VirtualKey(0x41), true);
+        assert_eq!(
+            unsafe { up.Anonymous.ki.dwFlags } & KEYEVENTF_KEYUP,
+            KEYEVENTF_KEYUP
+        );
+    }
+
+    #[test]
+    fn schedule_one_expands_repeat_into_separate_cycles() {
+        let press = Press::new(0x41_u16)
+            .with_duration(Duration::from_millis(10))
+            .with_repeat(3, Duration::from_millis(50));
+
+        let (events, next_clock) = schedule_one(&press, Duration::ZERO);
+
+        assert_eq!(events.len(), 6);
+        assert_eq!(events[0].at, Duration::ZERO);
+        assert!(!events[0].key_up);
+        assert_eq!(events[1].at, Duration::from_millis(10));
+        assert!(events[1].key_up);
+        assert_eq!(events[2].at, Duration::from_millis(60));
+        assert_eq!(events[4].at, Duration::from_millis(120));
+
+        // Clock is left at the final cycle's key-down.
+        assert_eq!(next_clock, Duration::from_millis(120));
+    }
+
+    #[test]
+    fn schedule_one_without_repeat_produces_a_single_cycle() {
+        let press = Press::new(0x41_u16).with_duration(Duration::from_millis(25));
+
+        let (events, next_clock) = schedule_one(&press, Duration::from_millis(5));
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].at, Duration::from_millis(5));
+        assert_eq!(events[1].at, Duration::from_millis(30));
+        assert_eq!(next_clock, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn schedule_sorts_downs_before_ups_at_the_same_instant() {
+        let (mut a, _) = schedule_one(&Press::new(0x41_u16), Duration::ZERO);
+        let (b, _) = schedule_one(&Press::new(0x42_u16), Duration::ZERO);
+        a.extend(b);
+
+        a.sort_by(|x, y| x.at.cmp(&y.at).then(x.key_up.cmp(&y.key_up)));
+
+        // Both presses' key-downs land at t=0 and must sort before either
+        // key-up scheduled for the same instant.
+        assert!(!a[0].key_up);
+        assert!(!a[1].key_up);
+        assert!(a[2].key_up);
+        assert!(a[3].key_up);
+    }
+}